@@ -1,25 +1,27 @@
 #![no_std]
 #![no_main]
 #![feature(custom_test_frameworks)]
-#![test_runner(crate::test_runner)]
+#![test_runner(rust_os::test_runner)]
 #![reexport_test_harness_main = "test_main"]
 
 use core::panic::PanicInfo;
+use rust_os::{clear_screen, println};
 
 extern crate rlibc;
 
-mod serial;
-mod vga_buffer;
-
 #[no_mangle]
 pub extern "C" fn _start() -> ! {
+    clear_screen!();
+
     println!("Hello, {}!", "World");
     println!("Another line!");
 
+    rust_os::init();
+
     #[cfg(test)]
     test_main();
 
-    loop {}
+    rust_os::hlt_loop();
 }
 
 #[cfg(not(test))]
@@ -27,44 +29,16 @@ pub extern "C" fn _start() -> ! {
 fn panic(info: &PanicInfo) -> ! {
     println!("{}", info);
 
-    loop {}
-}
-
-#[repr(u32)]
-pub enum QemuExitCode {
-    Success = 0x10,
-    Failed = 0x11,
-}
-
-pub fn exit_qemu(exit_code: QemuExitCode) {
-    unsafe {
-        let mut port = x86_64::instructions::port::Port::new(0xF4);
-        port.write(exit_code as u32);
-    }
+    rust_os::hlt_loop();
 }
 
 #[cfg(test)]
-fn test_runner(tests: &[&dyn Fn()]) {
-    serial_println!("Running {} tests", tests.len());
-    for test in tests {
-        test();
-    }
-    exit_qemu(QemuExitCode::Success);
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    rust_os::test_panic_handler(info)
 }
 
 #[test_case]
 fn trivial_assertion() {
-    serial_print!("Trivial assertion... ");
     assert_eq!(1, 0);
-    serial_println!("[ok]");
-}
-
-#[cfg(test)]
-#[panic_handler]
-fn panic(info: &PanicInfo) -> ! {
-    serial_println!("[failed]");
-    serial_println!("Error: {}", info);
-    exit_qemu(QemuExitCode::Failed);
-
-    loop {}
 }
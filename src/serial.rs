@@ -0,0 +1,67 @@
+use crate::text_buffer::TextBuffer;
+use core::fmt;
+use lazy_static::lazy_static;
+use spin::Mutex;
+use uart_16550::SerialPort as Uart;
+
+pub struct Writer {
+    port: Uart,
+}
+
+impl Writer {
+    fn new(mut port: Uart) -> Self {
+        port.init();
+        Self { port }
+    }
+}
+
+impl TextBuffer for Writer {
+    fn write_byte(&mut self, byte: u8) {
+        self.port.send(byte);
+    }
+
+    fn new_line(&mut self) {
+        self.write_byte(b'\n');
+    }
+
+    fn clear_row(&mut self, _row: usize) {
+        // The serial port is a plain byte stream; it has no fixed rows to blank.
+    }
+}
+
+impl fmt::Write for Writer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_string(s);
+        Ok(())
+    }
+}
+
+lazy_static! {
+    pub static ref SERIAL1: Mutex<Writer> = {
+        let port = unsafe { Uart::new(0x3F8) };
+        Mutex::new(Writer::new(port))
+    };
+}
+
+#[macro_export]
+macro_rules! serial_print {
+    ($($arg:tt)*) => ($crate::serial::_print(format_args!($($arg)*)));
+}
+
+#[macro_export]
+macro_rules! serial_println {
+    () => ($crate::serial_print!("\n"));
+    ($($arg:tt)*) => ($crate::serial_print!("{}\n", format_args!($($arg)*)));
+}
+
+#[doc(hidden)]
+pub fn _print(args: fmt::Arguments) {
+    use core::fmt::Write;
+
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        SERIAL1
+            .lock()
+            .write_fmt(args)
+            .expect("Printing to serial failed");
+    });
+}
@@ -0,0 +1,16 @@
+/// A sink that text can be written to a line at a time.
+///
+/// Implemented by both the VGA [`crate::vga_buffer::Writer`] and the serial
+/// [`crate::serial::Writer`] so callers can target either output generically.
+pub trait TextBuffer {
+    fn write_byte(&mut self, byte: u8);
+
+    fn write_string(&mut self, s: &str) {
+        for byte in s.bytes() {
+            self.write_byte(byte);
+        }
+    }
+
+    fn new_line(&mut self);
+    fn clear_row(&mut self, row: usize);
+}
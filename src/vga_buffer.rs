@@ -1,3 +1,4 @@
+use crate::text_buffer::TextBuffer;
 use core::fmt;
 use lazy_static::lazy_static;
 use spin::Mutex;
@@ -26,6 +27,48 @@ pub fn _print(args: fmt::Arguments) {
     });
 }
 
+#[macro_export]
+macro_rules! colour_print {
+    ($fg:expr, $bg:expr, $($arg:tt)*) => {
+        $crate::vga_buffer::_colour_print($fg, $bg, format_args!($($arg)*))
+    };
+}
+
+#[macro_export]
+macro_rules! colour_println {
+    ($fg:expr, $bg:expr) => ($crate::colour_print!($fg, $bg, "\n"));
+    ($fg:expr, $bg:expr, $($arg:tt)*) => {
+        $crate::colour_print!($fg, $bg, "{}\n", format_args!($($arg)*))
+    };
+}
+
+#[macro_export]
+macro_rules! clear_screen {
+    () => {
+        $crate::vga_buffer::_clear_screen()
+    };
+}
+
+#[doc(hidden)]
+pub fn _clear_screen() {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        WRITER.lock().clear_screen();
+    });
+}
+
+#[doc(hidden)]
+pub fn _colour_print(foreground: Colour, background: Colour, args: fmt::Arguments) {
+    use core::fmt::Write;
+
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        let previous_colour_code = writer.colour_code;
+        writer.set_colour(foreground, background);
+        writer.write_fmt(args).unwrap();
+        writer.colour_code = previous_colour_code;
+    });
+}
+
 #[allow(dead_code)]
 #[repr(u8)]
 pub enum Colour {
@@ -47,6 +90,31 @@ pub enum Colour {
     White = 15,
 }
 
+impl Colour {
+    /// Maps an SGR colour index (0-7) to the matching `Colour`, bumping it
+    /// into the bright half of the palette (8-15) when `bold` is set.
+    fn from_sgr(index: u8, bold: bool) -> Self {
+        match index + if bold { 8 } else { 0 } {
+            0 => Colour::Black,
+            1 => Colour::Red,
+            2 => Colour::Green,
+            3 => Colour::Brown,
+            4 => Colour::Blue,
+            5 => Colour::Magenta,
+            6 => Colour::Cyan,
+            7 => Colour::LightGrey,
+            8 => Colour::DarkGrey,
+            9 => Colour::LightRed,
+            10 => Colour::LightGreen,
+            11 => Colour::Yellow,
+            12 => Colour::LightBlue,
+            13 => Colour::Pink,
+            14 => Colour::LightCyan,
+            _ => Colour::White,
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 #[repr(transparent)]
 struct ColourCode(u8);
@@ -55,8 +123,27 @@ impl ColourCode {
     fn new(foreground: Colour, background: Colour) -> Self {
         Self((background as u8) << 4 | (foreground as u8))
     }
+
+    fn with_foreground(self, foreground: Colour) -> Self {
+        Self((self.0 & 0xF0) | (foreground as u8))
+    }
+
+    fn with_background(self, background: Colour) -> Self {
+        Self((self.0 & 0x0F) | (background as u8) << 4)
+    }
+}
+
+/// Parser state for CSI (`ESC [ ... letter`) escape sequences embedded in
+/// printed strings.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EscapeState {
+    Ground,
+    Escape,
+    Csi,
 }
 
+const MAX_CSI_PARAMS: usize = 4;
+
 #[derive(Clone, Copy)]
 #[repr(C)]
 struct ScreenChar {
@@ -73,6 +160,9 @@ pub struct Writer {
     cursor_column: usize,
     colour_code: ColourCode,
     buffer: &'static mut Buffer,
+    escape_state: EscapeState,
+    csi_params: [u16; MAX_CSI_PARAMS],
+    csi_param_count: usize,
 }
 
 lazy_static! {
@@ -80,20 +170,110 @@ lazy_static! {
         cursor_column: 0,
         colour_code: ColourCode::new(Colour::White, Colour::Black),
         buffer: unsafe { &mut *(0xB8000 as *mut Buffer) },
+        escape_state: EscapeState::Ground,
+        csi_params: [0; MAX_CSI_PARAMS],
+        csi_param_count: 0,
     });
 }
 
 impl Writer {
-    pub fn write_string(&mut self, s: &str) {
-        for byte in s.bytes() {
-            match byte {
+    pub fn set_colour(&mut self, foreground: Colour, background: Colour) {
+        self.colour_code = ColourCode::new(foreground, background);
+    }
+
+    pub fn clear_screen(&mut self) {
+        for row in 0..BUFFER_HEIGHT {
+            self.clear_row(row);
+        }
+        self.cursor_column = 0;
+        self.update_cursor(0, 0);
+    }
+
+    fn update_cursor(&self, row: usize, col: usize) {
+        use x86_64::instructions::port::Port;
+
+        let position = (row * BUFFER_WIDTH + col) as u16;
+        let mut index_port: Port<u8> = Port::new(0x3D4);
+        let mut data_port: Port<u8> = Port::new(0x3D5);
+
+        unsafe {
+            index_port.write(0x0F);
+            data_port.write((position & 0xFF) as u8);
+            index_port.write(0x0E);
+            data_port.write((position >> 8) as u8);
+        }
+    }
+
+    fn handle_byte(&mut self, byte: u8) {
+        match self.escape_state {
+            EscapeState::Ground if byte == 0x1B => self.escape_state = EscapeState::Escape,
+            EscapeState::Ground => match byte {
                 0x20..=0x7E | b'\n' => self.write_byte(byte),
                 _ => self.write_byte(0xFE),
+            },
+            EscapeState::Escape if byte == b'[' => {
+                self.csi_param_count = 0;
+                self.csi_params = [0; MAX_CSI_PARAMS];
+                self.escape_state = EscapeState::Csi;
             }
+            EscapeState::Escape => self.escape_state = EscapeState::Ground,
+            EscapeState::Csi => match byte {
+                b'0'..=b'9' => self.push_csi_digit(byte - b'0'),
+                b';' => self.end_csi_param(),
+                b'm' => {
+                    self.end_csi_param();
+                    self.apply_sgr_params();
+                    self.escape_state = EscapeState::Ground;
+                }
+                _ => self.escape_state = EscapeState::Ground,
+            },
         }
     }
 
-    pub fn write_byte(&mut self, byte: u8) {
+    fn push_csi_digit(&mut self, digit: u8) {
+        if let Some(param) = self.csi_params.get_mut(self.csi_param_count) {
+            *param = param.saturating_mul(10).saturating_add(u16::from(digit));
+        }
+    }
+
+    fn end_csi_param(&mut self) {
+        if self.csi_param_count < MAX_CSI_PARAMS {
+            self.csi_param_count += 1;
+        }
+    }
+
+    fn apply_sgr_params(&mut self) {
+        let mut bold = false;
+
+        for &param in &self.csi_params[..self.csi_param_count] {
+            match param {
+                0 => {
+                    self.colour_code = ColourCode::new(Colour::White, Colour::Black);
+                    bold = false;
+                }
+                1 => bold = true,
+                30..=37 => {
+                    let colour = Colour::from_sgr((param - 30) as u8, bold);
+                    self.colour_code = self.colour_code.with_foreground(colour);
+                }
+                40..=47 => {
+                    let colour = Colour::from_sgr((param - 40) as u8, bold);
+                    self.colour_code = self.colour_code.with_background(colour);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl TextBuffer for Writer {
+    fn write_string(&mut self, s: &str) {
+        for byte in s.bytes() {
+            self.handle_byte(byte);
+        }
+    }
+
+    fn write_byte(&mut self, byte: u8) {
         match byte {
             b'\n' => self.new_line(),
             byte => {
@@ -110,6 +290,7 @@ impl Writer {
                     colour_code,
                 });
                 self.cursor_column += 1;
+                self.update_cursor(row, self.cursor_column);
             }
         }
     }
@@ -133,6 +314,7 @@ impl Writer {
         for col in 0..BUFFER_WIDTH {
             self.buffer.chars[row][col].write(blank);
         }
+        self.update_cursor(row, 0);
     }
 }
 
@@ -213,6 +395,35 @@ fn can_clear_row() {
     });
 }
 
+#[test_case]
+fn ansi_escape_sets_colour() {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        println!();
+        print!("\x1b[31mERROR\x1b[0m");
+
+        let expected = ColourCode::new(Colour::Red, Colour::Black);
+        for (i, c) in "ERROR".chars().enumerate() {
+            let buffer_char = WRITER.lock().buffer.chars[BUFFER_HEIGHT - 1][i].read();
+            assert_eq!(char::from(buffer_char.ascii_char), c);
+            assert_eq!(buffer_char.colour_code.0, expected.0);
+        }
+
+        let reset = ColourCode::new(Colour::White, Colour::Black);
+        assert_eq!(WRITER.lock().colour_code.0, reset.0);
+    });
+}
+
+#[test_case]
+fn ansi_escape_bytes_are_not_printed() {
+    x86_64::instructions::interrupts::without_interrupts(|| {
+        println!();
+        print!("\x1b[31mA");
+
+        let buffer_char = WRITER.lock().buffer.chars[BUFFER_HEIGHT - 1][0].read();
+        assert_eq!(char::from(buffer_char.ascii_char), 'A');
+    });
+}
+
 #[test_case]
 fn print_with_newlines() {
     x86_64::instructions::interrupts::without_interrupts(|| {